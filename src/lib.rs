@@ -1,43 +1,264 @@
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::collections::VecDeque;
+use std::fmt;
 use std::str::FromStr;
 use wasm_bindgen::prelude::*;
 
-// Основна функція для обчислення виразу
+// Основна функція для обчислення виразу (без пам'яті про попередні виклики)
 #[wasm_bindgen]
 pub fn calculate(expression: &str) -> String {
-    match evaluate_expression(expression) {
-        Ok(result) => result.to_string(), // Повертаємо результат у вигляді рядка
-        Err(e) => e, // Повертаємо помилку
+    match evaluate_expression(expression, &HashMap::new(), 0.0) {
+        Ok(result) => {
+            clear_last_error();
+            result.to_string() // Повертаємо результат у вигляді рядка
+        }
+        Err(e) => {
+            record_last_error(&e);
+            e.to_string() // Повертаємо помилку
+        }
+    }
+}
+
+// Цілочисельний (програмістський) режим: модульна арифметика, побітові операції та довільна основа числення
+#[wasm_bindgen]
+pub fn calculate_int(expression: &str, base: u32) -> String {
+    match evaluate_expression_int(expression, base) {
+        Ok(result) => {
+            clear_last_error();
+            format_int(result, base) // Форматуємо результат у потрібній основі числення
+        }
+        Err(e) => {
+            record_last_error(&e);
+            e.to_string()
+        }
     }
 }
 
+thread_local! {
+    // Код і зсув останньої помилки, щоб фронтенд міг підсвітити проблемне місце у виразі
+    static LAST_ERROR: Cell<Option<(u32, i32)>> = const { Cell::new(None) };
+}
+
+fn record_last_error(err: &CalcError) {
+    LAST_ERROR.with(|cell| cell.set(Some((err.code(), err.offset().map(|o| o as i32).unwrap_or(-1)))));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|cell| cell.set(None));
+}
+
+// Код останньої помилки обчислення (0, якщо остання спроба була успішною)
+#[wasm_bindgen]
+pub fn last_error_code() -> u32 {
+    LAST_ERROR.with(|cell| cell.get().map(|(code, _)| code).unwrap_or(0))
+}
+
+// Байтовий зсув у вхідному рядку, де токенізація виявила помилку (-1, якщо недоступний)
+#[wasm_bindgen]
+pub fn last_error_offset() -> i32 {
+    LAST_ERROR.with(|cell| cell.get().map(|(_, offset)| offset).unwrap_or(-1))
+}
+
+// Калькулятор, що зберігає контекст між викликами: останній результат (`ans`) та змінні
+#[wasm_bindgen]
+pub struct Calculator {
+    variables: HashMap<String, f64>,
+    last_result: f64,
+}
+
+#[wasm_bindgen]
+impl Calculator {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Calculator {
+        Calculator {
+            variables: HashMap::new(),
+            last_result: 0.0,
+        }
+    }
+
+    // Обчислює вираз у контексті сесії: підтримує `ans`, іменовані змінні та присвоєння `x = ...`
+    pub fn eval(&mut self, expr: &str) -> String {
+        match self.eval_internal(expr) {
+            Ok(result) => {
+                clear_last_error();
+                result.to_string()
+            }
+            Err(e) => {
+                record_last_error(&e);
+                e.to_string()
+            }
+        }
+    }
+}
+
+impl Default for Calculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Calculator {
+    fn eval_internal(&mut self, expr: &str) -> Result<f64, CalcError> {
+        let result = if let Some((name, rhs)) = split_assignment(expr) {
+            let value = evaluate_expression(rhs, &self.variables, self.last_result)?;
+            self.variables.insert(name.to_string(), value);
+            value
+        } else {
+            evaluate_expression(expr, &self.variables, self.last_result)?
+        };
+        self.last_result = result;
+        Ok(result)
+    }
+}
+
+// Розбиває вираз виду `x = <вираз>` на ім'я змінної та праву частину.
+// Повертає `None`, якщо `=` відсутній, ліва частина — не коректне ім'я змінної, або це
+// зарезервоване ім'я: `ans` (його `tokenize` завжди читає як `Token::Ans`) чи назва
+// вбудованої функції.
+fn split_assignment(expr: &str) -> Option<(&str, &str)> {
+    let (name, rhs) = expr.split_once('=')?;
+    let name = name.trim();
+    let is_valid_name = !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase());
+    if is_valid_name && name != "ans" && lookup_function(name).is_none() {
+        Some((name, rhs))
+    } else {
+        None
+    }
+}
+
+// Різновиди помилок обчислення
+#[derive(Debug, Clone, PartialEq)]
+enum CalcErrorKind {
+    DivideByZero,
+    UnknownChar(char),
+    InvalidNumber(String),
+    MismatchedParens,
+    NotEnoughOperands,
+    DomainError(String),
+    UnknownFunction(String),
+    UnknownVariable(String),
+    UnknownOperator(char),
+    UnknownBase(u32),
+    Overflow,
+    InvalidToken,
+    InvalidExpression,
+}
+
+// Структурована помилка обчислення: тип помилки плюс, за наявності, байтовий зсув у
+// вхідному рядку, на якому зупинилась токенізація (для підсвічування у фронтенді).
+#[derive(Debug, Clone, PartialEq)]
+struct CalcError {
+    kind: CalcErrorKind,
+    offset: Option<usize>,
+}
+
+impl CalcError {
+    fn new(kind: CalcErrorKind) -> Self {
+        CalcError { kind, offset: None }
+    }
+
+    fn at(kind: CalcErrorKind, offset: usize) -> Self {
+        CalcError { kind, offset: Some(offset) }
+    }
+
+    fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    // Числовий код помилки для передачі у фронтенд через wasm-bindgen
+    fn code(&self) -> u32 {
+        match self.kind {
+            CalcErrorKind::DivideByZero => 1,
+            CalcErrorKind::UnknownChar(_) => 2,
+            CalcErrorKind::InvalidNumber(_) => 3,
+            CalcErrorKind::MismatchedParens => 4,
+            CalcErrorKind::NotEnoughOperands => 5,
+            CalcErrorKind::DomainError(_) => 6,
+            CalcErrorKind::UnknownFunction(_) => 7,
+            CalcErrorKind::UnknownVariable(_) => 8,
+            CalcErrorKind::UnknownOperator(_) => 9,
+            CalcErrorKind::UnknownBase(_) => 10,
+            CalcErrorKind::Overflow => 11,
+            CalcErrorKind::InvalidToken => 12,
+            CalcErrorKind::InvalidExpression => 13,
+        }
+    }
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            CalcErrorKind::DivideByZero => write!(f, "Помилка: Ділення на нуль."),
+            CalcErrorKind::UnknownChar(c) => write!(f, "Невідомий символ: {}", c),
+            CalcErrorKind::InvalidNumber(s) => write!(f, "Невірне число: {}", s),
+            CalcErrorKind::MismatchedParens => write!(f, "Незакриті дужки."),
+            CalcErrorKind::NotEnoughOperands => write!(f, "Недостатньо операндів для виконання операції."),
+            CalcErrorKind::DomainError(s) => write!(f, "Помилка області визначення: {}", s),
+            CalcErrorKind::UnknownFunction(s) => write!(f, "Невідома функція: {}", s),
+            CalcErrorKind::UnknownVariable(s) => write!(f, "Невідома змінна: {}", s),
+            CalcErrorKind::UnknownOperator(c) => write!(f, "Невідома операція: {}", c),
+            CalcErrorKind::UnknownBase(b) => write!(f, "Невідома основа числення: {}", b),
+            CalcErrorKind::Overflow => write!(f, "Помилка: Переповнення цілого числа."),
+            CalcErrorKind::InvalidToken => write!(f, "Невірний токен."),
+            CalcErrorKind::InvalidExpression => write!(f, "Невірний вираз."),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
 // Перелічення токенів, які можуть бути числом, оператором або дужкою
 #[derive(Debug, Clone)]
 enum Token {
     Number(f64),
+    IntNumber(i64),
     Operator(char),
+    Function(String),
+    Ans,
+    Variable(String),
     LeftParen,
     RightParen,
 }
 
-// Функція для оцінки математичного виразу
-fn evaluate_expression(expr: &str) -> Result<f64, String> {
+// Таблиця підтримуваних математичних функцій: ім'я -> реалізація
+fn lookup_function(name: &str) -> Option<fn(f64) -> f64> {
+    match name {
+        "sin" => Some(f64::sin),
+        "cos" => Some(f64::cos),
+        "tan" => Some(f64::tan),
+        "sqrt" => Some(f64::sqrt),
+        "ln" => Some(f64::ln),
+        "log" => Some(f64::log10),
+        "abs" => Some(f64::abs),
+        _ => None,
+    }
+}
+
+// Функція для оцінки математичного виразу в заданому контексті (змінні та `ans`)
+fn evaluate_expression(expr: &str, variables: &HashMap<String, f64>, last_result: f64) -> Result<f64, CalcError> {
     let tokens = tokenize(expr)?; // Токенізуємо вираз
     let rpn = to_rpn(tokens)?; // Перетворюємо токени в постфіксну нотацію
-    evaluate_rpn(&rpn) // Оцінюємо постфіксний вираз
+    evaluate_rpn(&rpn, variables, last_result) // Оцінюємо постфіксний вираз
+}
+
+// Чи перебуваємо ми в позиції, де `+`/`-` слід трактувати як унарний оператор
+// (початок виразу, одразу після іншого оператора або після лівої дужки)
+fn is_unary_position(prev: Option<&Token>) -> bool {
+    matches!(prev, None | Some(Token::Operator(_)) | Some(Token::LeftParen))
 }
 
 // Перетворення виразу у список токенів
-fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
+fn tokenize(expr: &str) -> Result<Vec<Token>, CalcError> {
     let mut tokens = Vec::new(); // Вектор для зберігання токенів
-    let mut chars = expr.chars().peekable(); // Ітератор по символах виразу
+    let mut chars = expr.char_indices().peekable(); // Ітератор по символах виразу з байтовими зсувами
 
-    while let Some(&c) = chars.peek() {
+    while let Some(&(idx, c)) = chars.peek() {
         match c {
             '0'..='9' | '.' => { // Обробка чисел і десяткових крапок
                 let mut number_str = String::new();
-                while let Some(&digit) = chars.peek() {
-                    if digit.is_digit(10) || digit == '.' {
+                while let Some(&(_, digit)) = chars.peek() {
+                    if digit.is_ascii_digit() || digit == '.' {
                         number_str.push(digit);
                         chars.next(); // Продовжуємо читати число
                     } else {
@@ -45,10 +266,18 @@ fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
                     }
                 }
                 // Перетворення рядка в число
-                let number = f64::from_str(&number_str).map_err(|_| format!("Невірне число: {}", number_str))?;
+                let number = f64::from_str(&number_str)
+                    .map_err(|_| CalcError::at(CalcErrorKind::InvalidNumber(number_str.clone()), idx))?;
                 tokens.push(Token::Number(number)); // Додаємо токен числа
             }
-            '+' | '-' | '*' | '/' => { // Обробка операторів
+            '+' | '-' if is_unary_position(tokens.last()) => {
+                // Унарний плюс нічого не змінює — просто пропускаємо його
+                if c == '-' {
+                    tokens.push(Token::Operator('~')); // Унарний мінус
+                }
+                chars.next();
+            }
+            '+' | '-' | '*' | '/' | '^' => { // Обробка операторів
                 tokens.push(Token::Operator(c)); // Додаємо токен оператора
                 chars.next(); // Переходимо до наступного символу
             }
@@ -63,7 +292,28 @@ fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
             ' ' => {
                 chars.next(); // Пропускаємо пробіли
             }
-            _ => return Err(format!("Невідомий символ: {}", c)), // Обробка невідомих символів
+            'a'..='z' => { // Обробка назв функцій, `ans` та змінних
+                let mut name = String::new();
+                while let Some(&(_, letter)) = chars.peek() {
+                    if letter.is_ascii_lowercase() {
+                        name.push(letter);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if chars.peek().map(|&(_, c)| c) == Some('(') {
+                    if lookup_function(&name).is_none() {
+                        return Err(CalcError::at(CalcErrorKind::UnknownFunction(name), idx));
+                    }
+                    tokens.push(Token::Function(name));
+                } else if name == "ans" {
+                    tokens.push(Token::Ans);
+                } else {
+                    tokens.push(Token::Variable(name));
+                }
+            }
+            _ => return Err(CalcError::at(CalcErrorKind::UnknownChar(c), idx)), // Обробка невідомих символів
         }
     }
     Ok(tokens) // Повертаємо токени
@@ -74,22 +324,34 @@ fn precedence(op: char) -> u8 {
     match op {
         '+' | '-' => 1, // Низький пріоритет
         '*' | '/' => 2, // Високий пріоритет
+        '^' => 3, // Вищий за множення й ділення (степінь)
+        '~' => 4, // Унарний мінус — найвищий пріоритет з усіх
         _ => 0, // Невідомий оператор
     }
 }
 
+// Чи є оператор лівоасоціативним (для правоасоціативних, напр. `^` і унарний `~`, повертає false)
+fn is_left_associative(op: char) -> bool {
+    op != '^' && op != '~'
+}
+
 // Перетворення інфіксного виразу у постфіксну нотацію (Reverse Polish Notation - RPN)
-fn to_rpn(tokens: Vec<Token>) -> Result<Vec<Token>, String> {
+fn to_rpn(tokens: Vec<Token>) -> Result<Vec<Token>, CalcError> {
     let mut output = Vec::new(); // Вектор для виходу
     let mut operators = VecDeque::new(); // Дек для операторів
 
     for token in tokens {
         match token {
-            Token::Number(_) => output.push(token), // Додаємо числа до виходу
+            Token::Number(_) | Token::Ans | Token::Variable(_) => output.push(token), // Додаємо значення до виходу
             Token::Operator(op) => {
-                // Обробка операторів за пріоритетом
+                // Обробка операторів за пріоритетом з урахуванням асоціативності
                 while let Some(Token::Operator(top_op)) = operators.back() {
-                    if precedence(*top_op) >= precedence(op) {
+                    let should_pop = if is_left_associative(op) {
+                        precedence(*top_op) >= precedence(op)
+                    } else {
+                        precedence(*top_op) > precedence(op)
+                    };
+                    if should_pop {
                         output.push(operators.pop_back().unwrap()); // Додаємо верхній оператор
                     } else {
                         break; // Вихід з циклу, якщо пріоритет нижчий
@@ -97,6 +359,7 @@ fn to_rpn(tokens: Vec<Token>) -> Result<Vec<Token>, String> {
                 }
                 operators.push_back(Token::Operator(op)); // Додаємо новий оператор
             }
+            Token::Function(_) => operators.push_back(token), // Функції чекають на стеку, як оператори з найвищим пріоритетом
             Token::LeftParen => operators.push_back(Token::LeftParen), // Додаємо ліву дужку
             Token::RightParen => {
                 // Обробка правої дужки
@@ -107,14 +370,19 @@ fn to_rpn(tokens: Vec<Token>) -> Result<Vec<Token>, String> {
                         output.push(op); // Додаємо оператор до виходу
                     }
                 }
+                // Якщо на стеку залишилась функція, що передувала дужці — переносимо її у вихід
+                if let Some(Token::Function(_)) = operators.back() {
+                    output.push(operators.pop_back().unwrap());
+                }
             }
+            Token::IntNumber(_) => return Err(CalcError::new(CalcErrorKind::InvalidToken)),
         }
     }
 
     // Додаємо залишилися оператори до виходу
     while let Some(op) = operators.pop_back() {
         if let Token::LeftParen = op {
-            return Err("Незакриті дужки.".to_string()); // Помилка для незакритих дужок
+            return Err(CalcError::new(CalcErrorKind::MismatchedParens)); // Помилка для незакритих дужок
         }
         output.push(op);
     }
@@ -122,17 +390,32 @@ fn to_rpn(tokens: Vec<Token>) -> Result<Vec<Token>, String> {
     Ok(output) // Повертаємо постфіксну нотацію
 }
 
-// Оцінка постфіксного виразу (RPN)
-fn evaluate_rpn(rpn: &[Token]) -> Result<f64, String> {
+// Оцінка постфіксного виразу (RPN) у заданому контексті (змінні та `ans`)
+fn evaluate_rpn(rpn: &[Token], variables: &HashMap<String, f64>, last_result: f64) -> Result<f64, CalcError> {
     let mut stack = Vec::new(); // Стек для обчислень
 
     for token in rpn {
         match token {
             Token::Number(num) => stack.push(*num), // Додаємо число до стека
+            Token::Ans => stack.push(last_result), // Підставляємо останній результат
+            Token::Variable(name) => {
+                let value = variables
+                    .get(name)
+                    .ok_or_else(|| CalcError::new(CalcErrorKind::UnknownVariable(name.clone())))?;
+                stack.push(*value);
+            }
+            Token::Operator('~') => {
+                // Унарний мінус — потребує лише одного операнда
+                if stack.is_empty() {
+                    return Err(CalcError::new(CalcErrorKind::NotEnoughOperands));
+                }
+                let a = stack.pop().unwrap();
+                stack.push(-a);
+            }
             Token::Operator(op) => {
                 // Обробка операторів
                 if stack.len() < 2 {
-                    return Err("Недостатньо операндів для виконання операції.".to_string());
+                    return Err(CalcError::new(CalcErrorKind::NotEnoughOperands));
                 }
                 let b = stack.pop().unwrap(); // Другий операнд
                 let a = stack.pop().unwrap(); // Перший операнд
@@ -142,21 +425,361 @@ fn evaluate_rpn(rpn: &[Token]) -> Result<f64, String> {
                     '*' => a * b,
                     '/' => {
                         if b == 0.0 {
-                            return Err("Помилка: Ділення на нуль.".to_string()); // Помилка для ділення на нуль
+                            return Err(CalcError::new(CalcErrorKind::DivideByZero)); // Помилка для ділення на нуль
                         }
                         a / b
                     }
-                    _ => return Err(format!("Невідома операція: {}", op)), // Обробка невідомих операторів
+                    '^' => a.powf(b), // Піднесення до степеня
+                    _ => return Err(CalcError::new(CalcErrorKind::UnknownOperator(*op))), // Обробка невідомих операторів
                 };
                 stack.push(result); // Додаємо результат обчислення до стека
             }
-            _ => return Err("Невірний токен.".to_string()), // Помилка для невірних токенів
+            Token::Function(name) => {
+                // Обробка функцій — потребують одного операнда
+                if stack.is_empty() {
+                    return Err(CalcError::new(CalcErrorKind::NotEnoughOperands));
+                }
+                let a = stack.pop().unwrap();
+                let func =
+                    lookup_function(name).ok_or_else(|| CalcError::new(CalcErrorKind::UnknownFunction(name.clone())))?;
+                match name.as_str() {
+                    "sqrt" if a < 0.0 => {
+                        return Err(CalcError::new(CalcErrorKind::DomainError("sqrt від'ємного числа.".to_string())))
+                    }
+                    "ln" | "log" if a <= 0.0 => {
+                        return Err(CalcError::new(CalcErrorKind::DomainError("логарифм недодатного числа.".to_string())))
+                    }
+                    _ => {}
+                }
+                stack.push(func(a));
+            }
+            _ => return Err(CalcError::new(CalcErrorKind::InvalidToken)), // Помилка для невірних токенів
         }
     }
 
     if stack.len() != 1 {
-        return Err("Невірний вираз.".to_string()); // Помилка для невірного виразу
+        return Err(CalcError::new(CalcErrorKind::InvalidExpression)); // Помилка для невірного виразу
     }
 
     Ok(stack[0]) // Повертаємо результат
 }
+
+// Функція для оцінки цілочисельного виразу у заданій основі числення
+fn evaluate_expression_int(expr: &str, base: u32) -> Result<i64, CalcError> {
+    if !(2..=36).contains(&base) {
+        return Err(CalcError::new(CalcErrorKind::UnknownBase(base)));
+    }
+    let tokens = tokenize_int(expr, base)?; // Токенізуємо вираз
+    let rpn = to_rpn_int(tokens)?; // Перетворюємо токени в постфіксну нотацію
+    evaluate_rpn_int(&rpn) // Оцінюємо постфіксний вираз
+}
+
+// Перетворення цілочисельного виразу у список токенів. Числові літерали читаються у
+// основі `base`, окрім префіксів `0x`/`0b`/`0o`, які завжди задають свою власну основу.
+fn tokenize_int(expr: &str, base: u32) -> Result<Vec<Token>, CalcError> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.char_indices().peekable();
+
+    while let Some(&(idx, c)) = chars.peek() {
+        match c {
+            _ if c.is_ascii_alphanumeric() => {
+                let mut literal = String::new();
+                let mut literal_base = base;
+                if c == '0' {
+                    chars.next();
+                    match chars.peek().map(|&(_, c)| c) {
+                        Some('x') | Some('X') => {
+                            chars.next();
+                            literal_base = 16;
+                        }
+                        Some('b') | Some('B') => {
+                            chars.next();
+                            literal_base = 2;
+                        }
+                        Some('o') | Some('O') => {
+                            chars.next();
+                            literal_base = 8;
+                        }
+                        _ => literal.push('0'),
+                    }
+                }
+                while let Some(&(_, digit)) = chars.peek() {
+                    if digit.is_digit(literal_base) {
+                        literal.push(digit);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if literal.is_empty() {
+                    return Err(CalcError::at(CalcErrorKind::UnknownChar(c), idx));
+                }
+                let number = i64::from_str_radix(&literal, literal_base)
+                    .map_err(|_| CalcError::at(CalcErrorKind::InvalidNumber(literal.clone()), idx))?;
+                tokens.push(Token::IntNumber(number));
+            }
+            '+' | '-' if is_unary_position(tokens.last()) => {
+                if c == '-' {
+                    tokens.push(Token::Operator('_')); // Внутрішній маркер унарного мінуса (щоб не плутати з побітовим `~`)
+                }
+                chars.next();
+            }
+            '+' | '-' | '*' | '/' | '%' | '&' | '|' | '^' | '~' => { // Арифметичні й побітові оператори
+                tokens.push(Token::Operator(c));
+                chars.next();
+            }
+            '<' => {
+                chars.next();
+                if chars.peek().map(|&(_, c)| c) == Some('<') {
+                    chars.next();
+                    tokens.push(Token::Operator('L')); // Зсув вліво
+                } else {
+                    return Err(CalcError::at(CalcErrorKind::UnknownChar('<'), idx));
+                }
+            }
+            '>' => {
+                chars.next();
+                if chars.peek().map(|&(_, c)| c) == Some('>') {
+                    chars.next();
+                    tokens.push(Token::Operator('R')); // Зсув вправо
+                } else {
+                    return Err(CalcError::at(CalcErrorKind::UnknownChar('>'), idx));
+                }
+            }
+            '(' => {
+                tokens.push(Token::LeftParen);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token::RightParen);
+                chars.next();
+            }
+            ' ' => {
+                chars.next();
+            }
+            _ => return Err(CalcError::at(CalcErrorKind::UnknownChar(c), idx)),
+        }
+    }
+    Ok(tokens)
+}
+
+// Пріоритет операцій у цілочисельному режимі (C-подібні рівні: побітові нижче арифметичних)
+fn precedence_int(op: char) -> u8 {
+    match op {
+        '~' | '_' => 7, // Унарні: побітове НІ та унарний мінус
+        '*' | '/' | '%' => 6,
+        '+' | '-' => 5,
+        'L' | 'R' => 4, // Зсуви
+        '&' => 3,
+        '^' => 2, // Виключне АБО
+        '|' => 1,
+        _ => 0,
+    }
+}
+
+// Чи є оператор цілочисельного режиму лівоасоціативним (унарні оператори — правоасоціативні)
+fn is_left_associative_int(op: char) -> bool {
+    op != '~' && op != '_'
+}
+
+// Перетворення цілочисельного інфіксного виразу у постфіксну нотацію
+fn to_rpn_int(tokens: Vec<Token>) -> Result<Vec<Token>, CalcError> {
+    let mut output = Vec::new();
+    let mut operators = VecDeque::new();
+
+    for token in tokens {
+        match token {
+            Token::IntNumber(_) => output.push(token),
+            Token::Operator(op) => {
+                while let Some(Token::Operator(top_op)) = operators.back() {
+                    let should_pop = if is_left_associative_int(op) {
+                        precedence_int(*top_op) >= precedence_int(op)
+                    } else {
+                        precedence_int(*top_op) > precedence_int(op)
+                    };
+                    if should_pop {
+                        output.push(operators.pop_back().unwrap());
+                    } else {
+                        break;
+                    }
+                }
+                operators.push_back(Token::Operator(op));
+            }
+            Token::LeftParen => operators.push_back(Token::LeftParen),
+            Token::RightParen => {
+                while let Some(op) = operators.pop_back() {
+                    if let Token::LeftParen = op {
+                        break;
+                    } else {
+                        output.push(op);
+                    }
+                }
+            }
+            _ => return Err(CalcError::new(CalcErrorKind::InvalidToken)),
+        }
+    }
+
+    while let Some(op) = operators.pop_back() {
+        if let Token::LeftParen = op {
+            return Err(CalcError::new(CalcErrorKind::MismatchedParens));
+        }
+        output.push(op);
+    }
+
+    Ok(output)
+}
+
+// Оцінка постфіксного цілочисельного виразу (RPN)
+fn evaluate_rpn_int(rpn: &[Token]) -> Result<i64, CalcError> {
+    let mut stack: Vec<i64> = Vec::new();
+
+    for token in rpn {
+        match token {
+            Token::IntNumber(num) => stack.push(*num),
+            Token::Operator('_') => {
+                // Унарний мінус — потребує лише одного операнда
+                let a = stack.pop().ok_or_else(|| CalcError::new(CalcErrorKind::NotEnoughOperands))?;
+                stack.push(a.checked_neg().ok_or_else(|| CalcError::new(CalcErrorKind::Overflow))?);
+            }
+            Token::Operator('~') => {
+                // Побітове НІ — потребує лише одного операнда
+                let a = stack.pop().ok_or_else(|| CalcError::new(CalcErrorKind::NotEnoughOperands))?;
+                stack.push(!a);
+            }
+            Token::Operator(op) => {
+                if stack.len() < 2 {
+                    return Err(CalcError::new(CalcErrorKind::NotEnoughOperands));
+                }
+                let b = stack.pop().unwrap();
+                let a = stack.pop().unwrap();
+                let overflow = || CalcError::new(CalcErrorKind::Overflow);
+                let result = match op {
+                    '+' => a.checked_add(b).ok_or_else(overflow)?,
+                    '-' => a.checked_sub(b).ok_or_else(overflow)?,
+                    '*' => a.checked_mul(b).ok_or_else(overflow)?,
+                    '/' => {
+                        if b == 0 {
+                            return Err(CalcError::new(CalcErrorKind::DivideByZero));
+                        }
+                        a / b
+                    }
+                    '%' => {
+                        if b == 0 {
+                            return Err(CalcError::new(CalcErrorKind::DivideByZero));
+                        }
+                        a % b
+                    }
+                    '&' => a & b,
+                    '|' => a | b,
+                    '^' => a ^ b,
+                    'L' => {
+                        let shift = u32::try_from(b).map_err(|_| overflow())?;
+                        a.checked_shl(shift).ok_or_else(overflow)?
+                    }
+                    'R' => {
+                        let shift = u32::try_from(b).map_err(|_| overflow())?;
+                        a.checked_shr(shift).ok_or_else(overflow)?
+                    }
+                    _ => return Err(CalcError::new(CalcErrorKind::UnknownOperator(*op))),
+                };
+                stack.push(result);
+            }
+            _ => return Err(CalcError::new(CalcErrorKind::InvalidToken)),
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err(CalcError::new(CalcErrorKind::InvalidExpression));
+    }
+
+    Ok(stack[0])
+}
+
+// Форматування цілого числа у рядок у заданій основі числення (2..=36)
+fn format_int(n: i64, base: u32) -> String {
+    if n == 0 {
+        return "0".to_string();
+    }
+    let negative = n < 0;
+    let mut value = n.unsigned_abs();
+    let mut digits = Vec::new();
+    while value > 0 {
+        let digit = (value % base as u64) as u32;
+        digits.push(std::char::from_digit(digit, base).unwrap());
+        value /= base as u64;
+    }
+    if negative {
+        digits.push('-');
+    }
+    digits.iter().rev().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponentiation_is_right_associative() {
+        assert_eq!(calculate("2^3^2"), "512"); // 2^(3^2), не (2^3)^2 = 64
+        assert_eq!(calculate("2^3"), "8");
+    }
+
+    #[test]
+    fn calculator_supports_ans_and_variable_bindings() {
+        let mut calc = Calculator::new();
+        assert_eq!(calc.eval("2 + 3"), "5");
+        assert_eq!(calc.eval("ans * 2"), "10"); // `ans` підставляє останній результат
+        assert_eq!(calc.eval("x = 7"), "7");
+        assert_eq!(calc.eval("x + 1"), "8"); // читання раніше збереженої змінної
+        assert!(calc.eval("y").contains("Невідома змінна"));
+        assert!(calc.eval("ans = 5").contains("Невідомий символ")); // `ans` — зарезервоване ім'я
+        assert!(calc.eval("sin = 5").contains("Невідомий символ")); // назва функції теж зарезервована
+    }
+
+    #[test]
+    fn unary_minus_and_plus_compose_with_binary_operators() {
+        assert_eq!(calculate("-5"), "-5");
+        assert_eq!(calculate("3 * -2"), "-6");
+        assert_eq!(calculate("-(2+1)"), "-3");
+        assert_eq!(calculate("+5"), "5");
+    }
+
+    #[test]
+    fn functions_apply_and_report_domain_errors() {
+        assert_eq!(calculate("sqrt(16)"), "4");
+        assert_eq!(calculate("2 * sqrt(4) + 1"), "5");
+        assert!(calculate("sqrt(-1)").contains("області визначення"));
+        assert!(calculate("ln(0)").contains("області визначення"));
+    }
+
+    #[test]
+    fn integer_mode_supports_bitwise_ops_and_arbitrary_bases() {
+        assert_eq!(calculate_int("10 % 3", 10), "1");
+        assert_eq!(calculate_int("5 & 3", 10), "1");
+        assert_eq!(calculate_int("5 | 2", 10), "7");
+        assert_eq!(calculate_int("1 << 4", 10), "16");
+        assert_eq!(calculate_int("~0", 10), "-1");
+        assert_eq!(calculate_int("0xff", 16), "ff"); // hex-режим виводить у тій самій основі
+        assert_eq!(calculate_int("0b1010", 2), "1010");
+        assert_eq!(calculate_int("0o17", 8), "17");
+        assert_eq!(calculate_int("ff", 16), "ff"); // цифри читаються у основі `base` без префікса
+    }
+
+    #[test]
+    fn integer_mode_rejects_base_out_of_range() {
+        assert!(calculate_int("1 + 2", 37).contains("основа"));
+        assert!(calculate_int("1 + 2", 1).contains("основа"));
+    }
+
+    #[test]
+    fn last_error_reports_code_and_tokenize_offset() {
+        assert_eq!(calculate("2 + 3"), "5");
+        assert_eq!(last_error_code(), 0); // успіх скидає стан помилки
+        assert_eq!(last_error_offset(), -1);
+
+        let result = calculate("2 + @");
+        assert!(result.contains("Невідомий символ"));
+        assert_eq!(last_error_code(), 2); // код CalcErrorKind::UnknownChar
+        assert_eq!(last_error_offset(), 4); // байтовий зсув символу `@`
+    }
+}